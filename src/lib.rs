@@ -1,9 +1,8 @@
 #![doc = include_str!("../README.md")]
 
-use std::borrow::Borrow;
 use std::borrow::Cow;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 use json_patch::Patch;
@@ -26,12 +25,31 @@ cfg_if::cfg_if! {
     }
 }
 
-/// A server signal update containing the signal type name and json patch.
+/// The wire format used to encode a [`ServerSignalUpdate`] before it is sent over the websocket.
+///
+/// `Json` sends the update as a JSON string over a text frame. It's the default, and is the
+/// easiest to inspect from devtools or a proxy. `Cbor` sends the update as CBOR bytes over a
+/// binary frame, which is more compact and cheaper to (de)serialize for large or
+/// frequently-updated signals, at the cost of no longer being human-readable on the wire.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Codec {
+    #[default]
+    Json,
+    Cbor,
+}
+
+/// A server signal update containing the signal type name, a per-signal sequence number, and a
+/// json patch.
 ///
 /// This is whats sent over the websocket, and is used to patch the signal if the type name matches.
+///
+/// `seq` is a per-signal counter, incremented by one for every update the server sends for that
+/// signal name. The client uses it to apply updates in order and detect gaps left by dropped or
+/// reordered frames, rather than assuming the websocket delivers messages reliably and in order.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ServerSignalUpdate {
     name: Cow<'static, str>,
+    seq: u64,
     patch: Patch,
 }
 
@@ -39,6 +57,7 @@ impl ServerSignalUpdate {
     /// Creates a new [`ServerSignalUpdate`] from an old and new instance of `T`.
     pub fn new<'s, 'e, T>(
         name: impl Into<Cow<'static, str>>,
+        seq: u64,
         old: &'s T,
         new: &'e T,
     ) -> Result<Self, serde_json::Error>
@@ -50,6 +69,7 @@ impl ServerSignalUpdate {
         let patch = json_patch::diff(&left, &right);
         Ok(ServerSignalUpdate {
             name: name.into(),
+            seq,
             patch,
         })
     }
@@ -57,15 +77,57 @@ impl ServerSignalUpdate {
     /// Creates a new [`ServerSignalUpdate`] from two json values.
     pub fn new_from_json<'s, 'e, T>(
         name: impl Into<Cow<'static, str>>,
+        seq: u64,
         old: &Value,
         new: &Value,
     ) -> Self {
         let patch = json_patch::diff(old, new);
         ServerSignalUpdate {
             name: name.into(),
+            seq,
             patch,
         }
     }
+
+    /// Serializes this update as a JSON string, to be sent over a text websocket frame.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Serializes this update as CBOR bytes, to be sent over a binary websocket frame.
+    pub fn to_cbor(&self) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+        let mut buf = Vec::new();
+        ciborium::ser::into_writer(self, &mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// A message sent from the client to the server over the signals websocket.
+///
+/// Unlike [`ServerSignalUpdate`], which flows server -> client, this only ever flows the other
+/// way, so it's kept as a separate, crate-private type rather than folded into the same enum.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum ClientMessage {
+    /// Sent right after the websocket (re)connects, and again whenever an update can't be applied
+    /// in order and local state is resynced, listing every signal this client has local state
+    /// for. A server integration is expected to answer with a fresh [`ServerSignalUpdate`] for
+    /// each one, encoded as a patch from the signal's `T::default()` value, so the
+    /// client has a known-good starting point before any further patches are applied. No such
+    /// integration exists yet, so until one does, a reconnect leaves local state reset and
+    /// unpopulated rather than actually resynced.
+    Hello { names: Vec<String> },
+    /// A periodic keep-alive, so the server can notice a half-open connection and close it,
+    /// which prompts the client to reconnect rather than silently missing updates forever.
+    Ping,
+    /// A write to a bidirectional signal, see [`create_bidirectional_signal`].
+    Update(ServerSignalUpdate),
+    /// Sent when local state for a signal name is created. Intended for a server integration to
+    /// narrow its broadcasts to names a connection actually has state for; no such filtering is
+    /// implemented yet, so this currently has no effect beyond the message being sent.
+    Subscribe { name: String },
+    /// Sent when local state for a signal name is torn down. Counterpart to
+    /// [`ClientMessage::Subscribe`], with the same caveat that nothing server-side acts on it yet.
+    Unsubscribe { name: String },
 }
 
 /// Provides a websocket url for server signals, if there is not already one provided.
@@ -81,13 +143,35 @@ impl ServerSignalUpdate {
 /// pub fn App(cx: Scope) -> impl IntoView {
 ///     // Provide websocket connection
 ///     leptos_server_signal::provide_websocket(cx, "ws://localhost:3000/ws").unwrap();
-///     
+///
 ///     // ...
 /// }
 /// ```
 #[allow(unused_variables)]
 pub fn provide_websocket(cx: Scope, url: &str) -> Result<(), JsValue> {
-    provide_websocket_inner(cx, url)
+    provide_websocket_inner(cx, url, Codec::Json)
+}
+
+/// Like [`provide_websocket`], but lets you pick the [`Codec`] used to encode signal updates.
+///
+/// The codec decides how this client encodes the messages it sends, so the server integration
+/// must be configured to decode them with the same codec. Incoming updates are decoded per
+/// message by frame type: text frames as JSON, binary frames as CBOR.
+///
+/// # Example
+///
+/// ```ignore
+/// #[component]
+/// pub fn App(cx: Scope) -> impl IntoView {
+///     // Provide websocket connection, sending and receiving CBOR instead of JSON
+///     leptos_server_signal::provide_websocket_with_codec(cx, "ws://localhost:3000/ws", Codec::Cbor).unwrap();
+///
+///     // ...
+/// }
+/// ```
+#[allow(unused_variables)]
+pub fn provide_websocket_with_codec(cx: Scope, url: &str, codec: Codec) -> Result<(), JsValue> {
+    provide_websocket_inner(cx, url, codec)
 }
 
 /// Creates a signal which is controlled by the server.
@@ -123,25 +207,12 @@ where
 
     cfg_if::cfg_if! {
         if #[cfg(target_arch = "wasm32")] {
-            use web_sys::MessageEvent;
-            use wasm_bindgen::{prelude::Closure, JsCast};
-            use leptos::{use_context, create_effect, SignalGet, SignalSet, SignalUpdate};
-            use js_sys::{Function, JsString};
-
-            let (json_get, json_set) = create_signal(cx, serde_json::to_value(T::default()).unwrap());
-            if let Some(ServerSignalWebSocket {state_signals: state_signals, ..}) = use_context::<ServerSignalWebSocket>(cx) {
-                state_signals.borrow_mut().insert(name.to_string(), (json_get, json_set));
-
-                // Note: The leptos docs advise against doing this. It seems to work
-                // well in testing, and the primary caveats are around unnecessary
-                // updates firing, but our state synchronization already prevents
-                // that on the server side
-                create_effect(cx, move |_| {
-                    let name = name.clone();
-                    let new_value = serde_json::from_value(json_get.get()).unwrap();
-                    set.set(new_value);
-                })
+            use leptos::use_context;
 
+            let default_json = serde_json::to_value(T::default()).unwrap();
+            let (json_get, json_set) = create_signal(cx, default_json.clone());
+            if let Some(ws) = use_context::<ServerSignalWebSocket>(cx) {
+                register_state_signal(cx, &ws, name, json_get, json_set, default_json, set);
             } else {
                 leptos::error!(
                     r#"server signal was used without a websocket being provided.
@@ -156,75 +227,648 @@ Ensure you call `leptos_server_signal::provide_websocket(cx, "ws://localhost:300
     get
 }
 
+/// Creates a signal that is synchronized bidirectionally with the server.
+///
+/// Updates from the server are applied just like [`create_server_signal`]. Local writes through
+/// the returned [`WriteSignal`] are diffed against the signal's last-known value and sent to the
+/// server as a [`ServerSignalUpdate`], over the same websocket `provide_websocket` set up.
+///
+/// This is the client half only: a server integration still needs to receive these updates,
+/// apply them to its authoritative copy of the named signal, and re-broadcast them to the other
+/// clients before writes actually propagate anywhere.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Clone, Default, Serialize, Deserialize)]
+/// pub struct Count {
+///     pub value: i32,
+/// }
+///
+/// #[component]
+/// pub fn App(cx: Scope) -> impl IntoView {
+///     // Create bidirectional server signal
+///     let (count, set_count) = create_bidirectional_signal::<Count>(cx, "counter");
+///
+///     view! { cx,
+///         <button on:click=move |_| set_count.update(|c| c.value += 1)>
+///             "Count: " {move || count().value.to_string()}
+///         </button>
+///     }
+/// }
+/// ```
+#[allow(unused_variables)]
+pub fn create_bidirectional_signal<T>(
+    cx: Scope,
+    name: impl Into<Cow<'static, str>>,
+) -> (ReadSignal<T>, WriteSignal<T>)
+where
+    T: Default + Serialize + for<'de> Deserialize<'de> + Clone,
+{
+    let name: Cow<'static, str> = name.into();
+    let (get, set) = create_signal(cx, T::default());
+
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            use leptos::{use_context, create_effect, SignalGet, SignalGetUntracked, SignalSet};
+
+            let default_json = serde_json::to_value(T::default()).unwrap();
+            let (json_get, json_set) = create_signal(cx, default_json.clone());
+            if let Some(ws) = use_context::<ServerSignalWebSocket>(cx) {
+                register_state_signal(cx, &ws, name.clone(), json_get, json_set, default_json, set);
+
+                // Mirrors the read effect set up by `register_state_signal`, but in the opposite
+                // direction: whenever the local value changes, diff it against the last-known json
+                // and push the difference upstream. If the change originated from the server (that
+                // read effect), the
+                // json is already up to date and the diff is empty, so nothing is sent.
+                let write_name = name.clone();
+                create_effect(cx, move |_| {
+                    let new_json = serde_json::to_value(get.get()).unwrap();
+                    let old_json = json_get.get_untracked();
+                    if new_json == old_json {
+                        return;
+                    }
+
+                    // `seq` is only meaningful for updates flowing server -> client; the server
+                    // assigns the authoritative sequence number when it rebroadcasts this write.
+                    let update =
+                        ServerSignalUpdate::new_from_json::<T>(write_name.clone(), 0, &old_json, &new_json);
+                    let connection = ws.connection.borrow();
+                    let sent = match &connection.ws {
+                        Some(socket) => ClientMessage::Update(update).send(socket, connection.codec),
+                        None => Err("not connected".to_string()),
+                    };
+
+                    match sent {
+                        Ok(()) => json_set.set(new_json),
+                        Err(err) => leptos::error!("Failed to send update for {}: {}", write_name, err),
+                    }
+                });
+            } else {
+                leptos::error!(
+                    r#"server signal was used without a websocket being provided.
+
+Ensure you call `leptos_server_signal::provide_websocket(cx, "ws://localhost:3000/ws")` at the highest level in your app."#
+                );
+            }
+        }
+    }
+
+    (get, set)
+}
+
+// Outcome of running an incoming update through `apply_seq_ordered`.
+#[derive(Debug, PartialEq, Eq)]
+enum SeqOutcome {
+    // Applied in order, possibly draining some now-contiguous buffered updates with it.
+    Applied,
+    // Arrived ahead of the update we're expecting; buffered until the gap fills in.
+    Buffered,
+    // A duplicate or an update we've already moved past.
+    Stale,
+    // The gap has grown past `max_buffered`, or a patch failed to apply; give up and resync
+    // instead.
+    ResyncNeeded,
+}
+
+// The seq-ordering and gap-detection logic behind `apply_update`, pulled out into a plain
+// function so it can be unit tested without a websocket or a leptos runtime.
+fn apply_seq_ordered(
+    seqs: &mut HashMap<String, u64>,
+    delayed: &mut HashMap<String, BTreeMap<u64, Patch>>,
+    doc: &mut Value,
+    name: &str,
+    update: ServerSignalUpdate,
+    max_buffered: usize,
+) -> SeqOutcome {
+    match seqs.get(name).copied() {
+        Some(last) if update.seq <= last => return SeqOutcome::Stale,
+        Some(last) if update.seq > last + 1 => {
+            let buffered = delayed.entry(name.to_string()).or_default();
+            buffered.insert(update.seq, update.patch);
+            return if buffered.len() > max_buffered {
+                SeqOutcome::ResyncNeeded
+            } else {
+                SeqOutcome::Buffered
+            };
+        }
+        // No baseline yet (the signal either just got local state, or we just resynced): this
+        // update becomes the new baseline unconditionally, since we have no prior seq to compare
+        // it against. Anything buffered at or below its seq is now stale and dropped, rather than
+        // applied out of order.
+        None => {
+            if json_patch::patch(doc, &update.patch).is_err() {
+                return SeqOutcome::ResyncNeeded;
+            }
+            seqs.insert(name.to_string(), update.seq);
+            if let Some(buffered) = delayed.get_mut(name) {
+                buffered.retain(|&seq, _| seq > update.seq);
+            }
+        }
+        _ => {
+            if json_patch::patch(doc, &update.patch).is_err() {
+                return SeqOutcome::ResyncNeeded;
+            }
+            seqs.insert(name.to_string(), update.seq);
+        }
+    }
+
+    // Drain any now-contiguous buffered updates following the one we just applied. A patch that
+    // fails to apply here is just as unrecoverable as one above - give up and resync rather than
+    // leaving the buffer stuck behind a patch that will never apply.
+    if let Some(buffered) = delayed.get_mut(name) {
+        let mut next = seqs.get(name).copied().unwrap_or(0) + 1;
+        while let Some(patch) = buffered.remove(&next) {
+            if json_patch::patch(doc, &patch).is_err() {
+                return SeqOutcome::ResyncNeeded;
+            }
+            seqs.insert(name.to_string(), next);
+            next += 1;
+        }
+        if buffered.is_empty() {
+            delayed.remove(name);
+        }
+    }
+
+    SeqOutcome::Applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Counter {
+        value: i32,
+    }
+
+    fn initial_doc() -> Value {
+        serde_json::to_value(Counter { value: 0 }).unwrap()
+    }
+
+    // `old` is always a value distinct from `value` so the diff is never empty.
+    fn update(name: &str, seq: u64, value: i32) -> ServerSignalUpdate {
+        ServerSignalUpdate::new(name.to_string(), seq, &Counter { value: -1 }, &Counter { value }).unwrap()
+    }
+
+    #[test]
+    fn applies_in_order_updates() {
+        let mut seqs = HashMap::new();
+        let mut delayed = HashMap::new();
+        let mut doc = initial_doc();
+
+        let outcome = apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 1, 1), 64);
+
+        assert_eq!(outcome, SeqOutcome::Applied);
+        assert_eq!(doc["value"], 1);
+        assert_eq!(seqs.get("counter"), Some(&1));
+    }
+
+    #[test]
+    fn drops_duplicate_and_stale_updates() {
+        let mut seqs = HashMap::new();
+        let mut delayed = HashMap::new();
+        let mut doc = initial_doc();
+        apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 1, 1), 64);
+
+        let outcome = apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 1, 2), 64);
+
+        assert_eq!(outcome, SeqOutcome::Stale);
+        assert_eq!(doc["value"], 1);
+        assert_eq!(seqs.get("counter"), Some(&1));
+    }
+
+    #[test]
+    fn buffers_reordered_updates_and_drains_once_the_gap_fills() {
+        let mut seqs = HashMap::new();
+        let mut delayed = HashMap::new();
+        let mut doc = initial_doc();
+        apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 1, 1), 64);
+
+        let outcome = apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 3, 3), 64);
+        assert_eq!(outcome, SeqOutcome::Buffered);
+        assert_eq!(doc["value"], 1);
+        assert_eq!(seqs.get("counter"), Some(&1));
+
+        let outcome = apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 2, 2), 64);
+        assert_eq!(outcome, SeqOutcome::Applied);
+        assert_eq!(doc["value"], 3);
+        assert_eq!(seqs.get("counter"), Some(&3));
+        assert!(delayed.get("counter").is_none());
+    }
+
+    #[test]
+    fn resyncs_once_the_gap_exceeds_the_buffer_limit() {
+        let mut seqs = HashMap::new();
+        let mut delayed = HashMap::new();
+        let mut doc = initial_doc();
+        apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 1, 1), 2);
+
+        apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 3, 3), 2);
+        apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 4, 4), 2);
+        let outcome = apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 5, 5), 2);
+
+        assert_eq!(outcome, SeqOutcome::ResyncNeeded);
+    }
+
+    #[test]
+    fn new_baseline_discards_stale_buffered_updates_instead_of_applying_them_out_of_order() {
+        let mut seqs = HashMap::new();
+        let mut delayed = HashMap::new();
+        let mut doc = initial_doc();
+
+        // Arrived before any local state existed for "counter", so these were buffered without a
+        // baseline. Seq 3 is missing, so applying 2 and then 4 in order would desync.
+        delayed
+            .entry("counter".to_string())
+            .or_insert_with(BTreeMap::new)
+            .insert(2, update("counter", 2, 2).patch);
+        delayed
+            .entry("counter".to_string())
+            .or_insert_with(BTreeMap::new)
+            .insert(4, update("counter", 4, 4).patch);
+
+        // Local state now exists, and the first update actually observed is seq 5.
+        let outcome = apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update("counter", 5, 5), 64);
+
+        assert_eq!(outcome, SeqOutcome::Applied);
+        assert_eq!(doc["value"], 5);
+        assert_eq!(seqs.get("counter"), Some(&5));
+        // The stale seq 2/4 entries are dropped rather than ever being applied.
+        assert!(delayed.get("counter").is_none());
+    }
+
+    #[test]
+    fn resyncs_when_a_patch_fails_to_apply() {
+        let mut seqs = HashMap::new();
+        let mut delayed = HashMap::new();
+        let mut doc = initial_doc();
+
+        // A well-formed `Patch`, but one whose path doesn't exist in `doc` - e.g. a patch computed
+        // against a differently-shaped document. `json_patch::patch` errors on this rather than
+        // panicking, and that error should surface as a resync instead of a crash.
+        let bad_patch: Patch = serde_json::from_value(serde_json::json!([
+            { "op": "replace", "path": "/missing/field", "value": 1 }
+        ]))
+        .unwrap();
+        let update = ServerSignalUpdate {
+            name: "counter".into(),
+            seq: 1,
+            patch: bad_patch,
+        };
+
+        let outcome = apply_seq_ordered(&mut seqs, &mut delayed, &mut doc, "counter", update, 64);
+
+        assert_eq!(outcome, SeqOutcome::ResyncNeeded);
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
         use web_sys::WebSocket;
         use leptos::{provide_context, use_context};
+        use wasm_bindgen::prelude::Closure;
+        use wasm_bindgen::JsCast;
+
+        // How often the client pings the server to detect a half-open connection.
+        const HEARTBEAT_INTERVAL_MS: i32 = 15_000;
+        // The reconnect delay doubles with each failed attempt, capped here.
+        const MAX_RECONNECT_DELAY_MS: i32 = 30_000;
+        // How many out-of-order updates we'll buffer for a signal while waiting for the gap
+        // before them to fill in. Once a signal has more than this many patches buffered, we give up
+        // waiting - the missing update is assumed lost for good - and resync from scratch.
+        const MAX_BUFFERED_UPDATES: usize = 64;
+
+        // The mutable half of `ServerSignalWebSocket`: everything that gets torn down and
+        // rebuilt across a reconnect. Kept behind a `RefCell` so reconnecting doesn't require
+        // re-providing the leptos context (which would orphan any signal already bound to it).
+        struct Connection {
+            // `None` from scheduling a reconnect until the backoff delay elapses and the retry
+            // creates the new websocket (which is stored before it has finished opening).
+            ws: Option<WebSocket>,
+            url: Rc<str>,
+            codec: Codec,
+            reconnect_attempts: u32,
+            heartbeat_id: Option<i32>,
+            reconnect_timeout_id: Option<i32>,
+        }
 
-        #[derive(Clone, Debug, PartialEq, Eq)]
+        #[derive(Clone)]
         struct ServerSignalWebSocket {
-            ws: WebSocket,
+            connection: Rc<RefCell<Connection>>,
             // References to these are kept by the closure for the callback
-            // onmessage callback on the websocket
-            state_signals: Rc<RefCell<HashMap<String, (ReadSignal<serde_json::Value>, WriteSignal<serde_json::Value>)>>>,
+            // onmessage callback on the websocket. The third element is the signal's
+            // default-shaped JSON (`T::default()`, stashed at creation time), used by
+            // `reset_for_resync` so a reset doc still matches the shape a hello-response patch is
+            // computed against, instead of an untyped `Value::Null`.
+            state_signals: Rc<RefCell<HashMap<String, (ReadSignal<serde_json::Value>, WriteSignal<serde_json::Value>, serde_json::Value)>>>,
             // When the websocket is first established, the leptos may not have
             // completed the traversal that sets up all of the state signals.
             // Without that, we don't have a base state to apply the patches to,
             // and therefore we must keep a record of the patches to apply after
-            // the state has been set up.
-            delayed_updates: Rc<RefCell<HashMap<String, Vec<Patch>>>>,
+            // the state has been set up. Also doubles as the out-of-order buffer for updates
+            // that arrived with a seq past the next one we're expecting, keyed by seq so they
+            // drain back out in order once the gap fills in. Patches queued before a signal has
+            // local state are not replayed onto it: the first update it applies without a
+            // baseline drops every buffered patch at or below its own seq (see
+            // `apply_seq_ordered`).
+            delayed_updates: Rc<RefCell<HashMap<String, BTreeMap<u64, Patch>>>>,
+            // The last seq successfully applied for each signal name, used to apply updates in
+            // order and detect gaps left by dropped or reordered frames. Absence of an entry
+            // means the next update for that signal should be accepted unconditionally, which is
+            // true both before the first update and right after a resync.
+            last_applied_seq: Rc<RefCell<HashMap<String, u64>>>,
         }
 
-        #[inline]
-        fn provide_websocket_inner(cx: Scope, url: &str) -> Result<(), JsValue> {
+        impl ClientMessage {
+            fn send(&self, ws: &WebSocket, codec: Codec) -> Result<(), String> {
+                match codec {
+                    Codec::Json => serde_json::to_string(self)
+                        .map_err(|err| err.to_string())
+                        .and_then(|text| ws.send_with_str(&text).map_err(|err| format!("{err:?}"))),
+                    Codec::Cbor => {
+                        let mut buf = Vec::new();
+                        ciborium::ser::into_writer(self, &mut buf).map_err(|err| err.to_string())?;
+                        ws.send_with_u8_array(&buf).map_err(|err| format!("{err:?}"))
+                    }
+                }
+            }
+        }
+
+        // Decodes either a JSON text frame or a CBOR binary frame into a `ServerSignalUpdate`.
+        fn decode_update(event: &web_sys::MessageEvent) -> Option<ServerSignalUpdate> {
+            use js_sys::{ArrayBuffer, JsString, Uint8Array};
+
+            let data = event.data();
+            if let Ok(text) = data.clone().dyn_into::<JsString>() {
+                serde_json::from_str(&String::from(text)).ok()
+            } else if let Ok(buf) = data.dyn_into::<ArrayBuffer>() {
+                let bytes = Uint8Array::new(&buf).to_vec();
+                ciborium::de::from_reader(bytes.as_slice()).ok()
+            } else {
+                None
+            }
+        }
+
+        type StateSignals = Rc<RefCell<HashMap<String, (ReadSignal<serde_json::Value>, WriteSignal<serde_json::Value>, serde_json::Value)>>>;
+
+        // Resets every cached doc back to its own default-shaped JSON (not `Value::Null` - the
+        // hello-response snapshot patch is computed against `T::default()`, so the doc it's applied
+        // to needs that same shape) and drops buffered patches/seqs, so a hello response applies
+        // cleanly instead of layering onto stale state.
+        fn reset_for_resync(ws: &ServerSignalWebSocket) {
+            use leptos::SignalSet;
+
+            for (_, json_set, default_json) in ws.state_signals.borrow().values() {
+                json_set.set(default_json.clone());
+            }
+            ws.delayed_updates.borrow_mut().clear();
+            ws.last_applied_seq.borrow_mut().clear();
+        }
+
+        // Sends `hello` listing every signal this client currently has local state for.
+        fn send_hello(connection: &Connection, state_signals: &StateSignals) {
+            let Some(ws) = &connection.ws else { return };
+            let names = state_signals.borrow().keys().cloned().collect();
+            if let Err(err) = (ClientMessage::Hello { names }).send(ws, connection.codec) {
+                leptos::error!("Failed to send hello: {}", err);
+            }
+        }
+
+        // Registers `(json_get, json_set)` as the backing store for `name`, subscribes to updates
+        // for it, wires up the read effect that mirrors incoming patches into `set`, and tears all
+        // of that down on cleanup. Shared by `create_server_signal` and `create_bidirectional_signal`.
+        fn register_state_signal<T>(
+            cx: Scope,
+            ws: &ServerSignalWebSocket,
+            name: Cow<'static, str>,
+            json_get: ReadSignal<Value>,
+            json_set: WriteSignal<Value>,
+            default_json: Value,
+            set: WriteSignal<T>,
+        ) where
+            T: for<'de> Deserialize<'de> + 'static,
+        {
+            use leptos::{create_effect, on_cleanup, SignalGet};
+
+            ws.state_signals
+                .borrow_mut()
+                .insert(name.to_string(), (json_get, json_set, default_json));
+            subscribe(ws, name.to_string());
+
+            let cleanup_ws = ws.clone();
+            let cleanup_name = name.to_string();
+            on_cleanup(cx, move || {
+                cleanup_ws.state_signals.borrow_mut().remove(&cleanup_name);
+                unsubscribe(&cleanup_ws, cleanup_name);
+            });
+
+            // Note: The leptos docs advise against doing this. It seems to work
+            // well in testing, and the primary caveats are around unnecessary
+            // updates firing, but our state synchronization already prevents
+            // that on the server side
+            create_effect(cx, move |_| {
+                let new_value = serde_json::from_value(json_get.get()).unwrap();
+                set.set(new_value);
+            });
+        }
+
+        // See `ClientMessage::Subscribe` for the current scope.
+        fn subscribe(ws: &ServerSignalWebSocket, name: String) {
+            let connection = ws.connection.borrow();
+            let Some(socket) = &connection.ws else { return };
+            if let Err(err) = (ClientMessage::Subscribe { name }).send(socket, connection.codec) {
+                leptos::error!("Failed to send subscribe: {}", err);
+            }
+        }
+
+        // See `ClientMessage::Unsubscribe` for the current scope.
+        fn unsubscribe(ws: &ServerSignalWebSocket, name: String) {
+            let connection = ws.connection.borrow();
+            let Some(socket) = &connection.ws else { return };
+            if let Err(err) = (ClientMessage::Unsubscribe { name }).send(socket, connection.codec) {
+                leptos::error!("Failed to send unsubscribe: {}", err);
+            }
+        }
+
+        // Resets local state and re-requests a snapshot, without tearing down the websocket.
+        fn resync(ws: &ServerSignalWebSocket) {
+            reset_for_resync(ws);
+            send_hello(&ws.connection.borrow(), &ws.state_signals);
+        }
+
+        // Applies `update` via `apply_seq_ordered`, logging and translating the outcome into
+        // whether a full resync should be triggered.
+        fn apply_update(ws: &ServerSignalWebSocket, update: ServerSignalUpdate) -> bool {
+            use leptos::SignalUpdate;
+
+            // Owned, rather than borrowed from `update.name`, since `update` is moved whole into
+            // `apply_seq_ordered` below.
+            let name = update.name.to_string();
+
+            let handler_map = ws.state_signals.borrow();
+            let Some((_, json_set, _)) = handler_map.get(&name) else {
+                leptos::warn!("No local state for update to {}. Queuing patch.", name);
+                ws.delayed_updates
+                    .borrow_mut()
+                    .entry(name)
+                    .or_default()
+                    .insert(update.seq, update.patch);
+                return false;
+            };
+
+            let mut seqs = ws.last_applied_seq.borrow_mut();
+            let mut delayed_map = ws.delayed_updates.borrow_mut();
+            let seq = update.seq;
+
+            let mut outcome = SeqOutcome::Applied;
+            json_set.update(|doc| {
+                outcome = apply_seq_ordered(&mut seqs, &mut delayed_map, doc, &name, update, MAX_BUFFERED_UPDATES);
+            });
+
+            match outcome {
+                SeqOutcome::Applied | SeqOutcome::Buffered => false,
+                SeqOutcome::Stale => {
+                    leptos::warn!("Dropping duplicate or stale update {} for {}", seq, name);
+                    false
+                }
+                SeqOutcome::ResyncNeeded => {
+                    leptos::warn!("Missing or unappliable updates to {}, resyncing", name);
+                    true
+                }
+            }
+        }
+
+        fn start_heartbeat(ws: ServerSignalWebSocket) {
+            stop_heartbeat(&ws);
+            let heartbeat_ws = ws.clone();
+            let tick = Closure::wrap(Box::new(move || {
+                let connection = heartbeat_ws.connection.borrow();
+                let Some(socket) = &connection.ws else { return };
+                if let Err(err) = ClientMessage::Ping.send(socket, connection.codec) {
+                    leptos::error!("Failed to send heartbeat ping: {}", err);
+                }
+            }) as Box<dyn FnMut()>);
+            let window = web_sys::window().expect("a window, since server signals only run client-side");
+            let id = window
+                .set_interval_with_callback_and_timeout_and_arguments_0(
+                    tick.as_ref().unchecked_ref(),
+                    HEARTBEAT_INTERVAL_MS,
+                )
+                .expect("setInterval should not fail");
+            tick.forget();
+            ws.connection.borrow_mut().heartbeat_id = Some(id);
+        }
+
+        fn stop_heartbeat(ws: &ServerSignalWebSocket) {
+            let mut connection = ws.connection.borrow_mut();
+            if let Some(id) = connection.heartbeat_id.take() {
+                if let Some(window) = web_sys::window() {
+                    window.clear_interval_with_handle(id);
+                }
+            }
+        }
+
+        // Creates a websocket and wires up its handlers; used both for the first connection and
+        // for every reconnect.
+        fn open_socket(cx: Scope, ws: ServerSignalWebSocket) -> Result<(), JsValue> {
             use web_sys::MessageEvent;
-            use wasm_bindgen::{prelude::Closure, JsCast};
-            use leptos::{use_context, create_effect, SignalGetUntracked, SignalSet, SignalUpdate};
-            use js_sys::{Function, JsString};
 
-            if use_context::<ServerSignalWebSocket>(cx).is_none() {
-                let ws = WebSocket::new(url)?;
-                provide_context(cx, ServerSignalWebSocket{ws: ws, state_signals: Rc::default(), delayed_updates: Rc::default()});
-            }
-
-            let ws = use_context::<ServerSignalWebSocket>(cx).unwrap();
-
-            let handlers = ws.state_signals.clone();
-            let delayed_updates = ws.delayed_updates.clone();
-
-            let callback = Closure::wrap(Box::new(move |event: MessageEvent| {
-                let ws_string = event.data().dyn_into::<JsString>().unwrap().as_string().unwrap();
-                if let Ok(update_signal) = serde_json::from_str::<ServerSignalUpdate>(&ws_string) {
-                    let handler_map = (*handlers).borrow();
-                    let name = update_signal.name.borrow();
-                    let mut delayed_map = (*delayed_updates).borrow_mut();
-                    if let Some((json_get, json_set)) = handler_map.get::<str>(name) {
-                        if let Some(delayed_patches) = delayed_map.remove(name) {
-                            json_set.update(|doc| {
-                                for patch in delayed_patches {
-                                    json_patch::patch(doc, &patch).unwrap();
-                                }
-                            });
+            let url = ws.connection.borrow().url.clone();
+            let socket = WebSocket::new(&url)?;
+            socket.set_binary_type(web_sys::BinaryType::Arraybuffer);
+
+            {
+                let ws = ws.clone();
+                let onopen = Closure::wrap(Box::new(move || {
+                    ws.connection.borrow_mut().reconnect_attempts = 0;
+                    start_heartbeat(ws.clone());
+                    reset_for_resync(&ws);
+                    send_hello(&ws.connection.borrow(), &ws.state_signals);
+                }) as Box<dyn FnMut()>);
+                socket.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+                onopen.forget();
+            }
+
+            {
+                let ws = ws.clone();
+                let onmessage = Closure::wrap(Box::new(move |event: MessageEvent| {
+                    if let Some(update_signal) = decode_update(&event) {
+                        if apply_update(&ws, update_signal) {
+                            resync(&ws);
                         }
-                        json_set.update(|doc| {
-                            json_patch::patch(doc, &update_signal.patch).unwrap();
-                        });
-                    } else {
-                        leptos::warn!("No local state for update to {}. Queuing patch.", name);
-                        delayed_map.entry(name.into()).or_default().push(update_signal.patch.clone());
                     }
+                }) as Box<dyn FnMut(_)>);
+                socket.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+                onmessage.forget();
+            }
+
+            {
+                let ws = ws.clone();
+                let onclose = Closure::wrap(Box::new(move || {
+                    stop_heartbeat(&ws);
+                    schedule_reconnect(cx, ws.clone());
+                }) as Box<dyn FnMut()>);
+                socket.set_onclose(Some(onclose.as_ref().unchecked_ref()));
+                onclose.forget();
+            }
+
+            ws.connection.borrow_mut().ws = Some(socket);
+            Ok(())
+        }
+
+        // Schedules a reconnect attempt after an exponentially increasing delay.
+        fn schedule_reconnect(cx: Scope, ws: ServerSignalWebSocket) {
+            let attempt = {
+                let mut connection = ws.connection.borrow_mut();
+                connection.ws = None;
+                let attempt = connection.reconnect_attempts;
+                connection.reconnect_attempts = attempt.saturating_add(1);
+                attempt
+            };
+            let delay_ms = 1_000i32.saturating_mul(1i32 << attempt.min(5)).min(MAX_RECONNECT_DELAY_MS);
+
+            let retry_ws = ws.clone();
+            let retry = Closure::wrap(Box::new(move || {
+                if let Err(err) = open_socket(cx, retry_ws.clone()) {
+                    leptos::error!("Failed to reconnect: {:?}", err);
+                    schedule_reconnect(cx, retry_ws.clone());
                 }
-            }) as Box<dyn FnMut(_)>);
-            let function: &Function = callback.as_ref().unchecked_ref();
-            ws.ws.set_onmessage(Some(function));
+            }) as Box<dyn FnMut()>);
+            let window = web_sys::window().expect("a window, since server signals only run client-side");
+            let id = window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(retry.as_ref().unchecked_ref(), delay_ms)
+                .expect("setTimeout should not fail");
+            retry.forget();
+            ws.connection.borrow_mut().reconnect_timeout_id = Some(id);
+        }
+
+        #[inline]
+        fn provide_websocket_inner(cx: Scope, url: &str, codec: Codec) -> Result<(), JsValue> {
+            if use_context::<ServerSignalWebSocket>(cx).is_none() {
+                let ws = ServerSignalWebSocket {
+                    connection: Rc::new(RefCell::new(Connection {
+                        ws: None,
+                        url: Rc::from(url),
+                        codec,
+                        reconnect_attempts: 0,
+                        heartbeat_id: None,
+                        reconnect_timeout_id: None,
+                    })),
+                    state_signals: Rc::default(),
+                    delayed_updates: Rc::default(),
+                    last_applied_seq: Rc::default(),
+                };
+                open_socket(cx, ws.clone())?;
+                provide_context(cx, ws);
+            }
 
-            // Keep the closure alive for the lifetime of the program
-            callback.forget();
             Ok(())
         }
     } else {
         #[inline]
-        fn provide_websocket_inner(_cx: Scope, _url: &str) -> Result<(), JsValue> {
+        fn provide_websocket_inner(_cx: Scope, _url: &str, _codec: Codec) -> Result<(), JsValue> {
             Ok(())
         }
     }